@@ -8,8 +8,20 @@ pub enum Error {
     ParseResourceInfo(#[from] quick_xml::de::DeError),
     #[error("{0}")]
     InternalError(String),
+    #[error("Hash mismatch for {path}: expected {expected}, got {actual}")]
+    HashMismatch {
+        path: String,
+        expected: String,
+        actual: String,
+    },
+    #[error("Unsupported digest algorithm: {0}")]
+    UnsupportedAlgo(String),
     #[error("{0}")]
     Reqwest(#[from] reqwest::Error),
     #[error("{0}")]
     Io(#[from] std::io::Error),
+    #[error("{0}")]
+    DownloadsFailed(String),
+    #[error("{0}")]
+    VerificationFailed(String),
 }
@@ -0,0 +1,143 @@
+use crate::error::Error;
+use sha2::{Digest, Sha256};
+
+/// A digest algorithm picked per-file, so the streaming download path stays
+/// algorithm-agnostic.
+pub enum Hasher {
+    Sha256(Sha256),
+    Blake3(blake3::Hasher),
+}
+
+impl Hasher {
+    /// Picks an algorithm for `sum`/`algo` and returns the hasher alongside
+    /// the digest to compare the final hash against (with any algorithm
+    /// prefix stripped off).
+    ///
+    /// `algo` (an explicit per-file override) wins if present; otherwise a
+    /// `blake3:` prefix on `sum` selects BLAKE3, and anything else defaults
+    /// to SHA-256. An `algo` that names neither is a manifest error rather
+    /// than a silent fallback, so a typo like `"sha1"` doesn't masquerade as
+    /// a hash mismatch against the wrong algorithm.
+    pub fn for_sum(sum: &str, algo: Option<&str>) -> Result<(Self, String), Error> {
+        if let Some(algo) = algo {
+            return match algo.to_ascii_lowercase().as_str() {
+                "blake3" => Ok((Self::Blake3(blake3::Hasher::new()), Self::strip_prefix(sum).to_owned())),
+                "sha256" => Ok((Self::Sha256(Sha256::new()), Self::strip_prefix(sum).to_owned())),
+                _ => Err(Error::UnsupportedAlgo(algo.to_owned())),
+            };
+        }
+
+        match sum.strip_prefix("blake3:") {
+            Some(hex) => Ok((Self::Blake3(blake3::Hasher::new()), hex.to_owned())),
+            None => Ok((Self::Sha256(Sha256::new()), sum.to_owned())),
+        }
+    }
+
+    /// Returns the digest to compare the final hash against, without
+    /// constructing a hasher.
+    pub fn expected_sum(sum: &str) -> &str {
+        Self::strip_prefix(sum)
+    }
+
+    fn strip_prefix(sum: &str) -> &str {
+        sum.strip_prefix("blake3:").unwrap_or(sum)
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Sha256(hasher) => hasher.update(data),
+            Self::Blake3(hasher) => {
+                hasher.update(data);
+            }
+        }
+    }
+
+    pub fn finalize(self) -> String {
+        match self {
+            Self::Sha256(hasher) => format!("{:x}", hasher.finalize()),
+            Self::Blake3(hasher) => hasher.finalize().to_hex().to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finalize_of(hasher: Hasher, data: &[u8]) -> String {
+        let mut hasher = hasher;
+        hasher.update(data);
+        hasher.finalize()
+    }
+
+    #[test]
+    fn defaults_to_sha256_for_a_plain_sum() {
+        let (hasher, expected) = Hasher::for_sum("deadbeef", None).unwrap();
+        assert!(matches!(hasher, Hasher::Sha256(_)));
+        assert_eq!(expected, "deadbeef");
+    }
+
+    #[test]
+    fn blake3_prefix_on_sum_selects_blake3_and_strips_the_prefix() {
+        let (hasher, expected) = Hasher::for_sum("blake3:deadbeef", None).unwrap();
+        assert!(matches!(hasher, Hasher::Blake3(_)));
+        assert_eq!(expected, "deadbeef");
+    }
+
+    #[test]
+    fn explicit_algo_field_overrides_the_sum_prefix() {
+        let (hasher, expected) = Hasher::for_sum("deadbeef", Some("blake3")).unwrap();
+        assert!(matches!(hasher, Hasher::Blake3(_)));
+        assert_eq!(expected, "deadbeef");
+
+        let (hasher, expected) = Hasher::for_sum("blake3:deadbeef", Some("sha256")).unwrap();
+        assert!(matches!(hasher, Hasher::Sha256(_)));
+        assert_eq!(expected, "deadbeef");
+    }
+
+    #[test]
+    fn algo_field_is_case_insensitive() {
+        let (hasher, _) = Hasher::for_sum("deadbeef", Some("BLAKE3")).unwrap();
+        assert!(matches!(hasher, Hasher::Blake3(_)));
+    }
+
+    #[test]
+    fn unrecognized_algo_field_is_an_error_instead_of_a_silent_sha256_fallback() {
+        let err = Hasher::for_sum("deadbeef", Some("sha1")).unwrap_err();
+        assert!(matches!(err, Error::UnsupportedAlgo(algo) if algo == "sha1"));
+    }
+
+    #[test]
+    fn sha256_digest_matches_the_well_known_test_vector_for_an_empty_input() {
+        let (hasher, _) = Hasher::for_sum("", None).unwrap();
+        assert_eq!(
+            finalize_of(hasher, b""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn sha256_and_blake3_produce_different_but_deterministic_digests() {
+        let (sha_hasher, _) = Hasher::for_sum("", None).unwrap();
+        let (blake_hasher, _) = Hasher::for_sum("blake3:", None).unwrap();
+
+        let sha_digest = finalize_of(sha_hasher, b"hello");
+        let blake_digest = finalize_of(blake_hasher, b"hello");
+
+        // Both are 32-byte digests hex-encoded, but the two algorithms must
+        // not agree on the same input.
+        assert_eq!(sha_digest.len(), 64);
+        assert_eq!(blake_digest.len(), 64);
+        assert_ne!(sha_digest, blake_digest);
+
+        // Hashing is deterministic: the same input always yields the same digest.
+        let (sha_hasher_again, _) = Hasher::for_sum("", None).unwrap();
+        assert_eq!(finalize_of(sha_hasher_again, b"hello"), sha_digest);
+    }
+
+    #[test]
+    fn expected_sum_strips_the_blake3_prefix_without_allocating_a_hasher() {
+        assert_eq!(Hasher::expected_sum("deadbeef"), "deadbeef");
+        assert_eq!(Hasher::expected_sum("blake3:deadbeef"), "deadbeef");
+    }
+}
@@ -1,21 +1,31 @@
 mod error;
+mod hash;
 mod reporter;
 mod resources;
 
 use std::{
     fmt::{Debug, Formatter},
-    path::PathBuf,
-    sync::Arc,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    time::Duration,
 };
 
 use bon::Builder;
 use clap::Parser;
 pub use error::*;
+use futures_util::StreamExt;
+use hash::Hasher;
 pub use reporter::*;
 use reqwest::Client;
 pub use resources::*;
-use sha2::{Digest, Sha256};
-use tokio::{runtime::Runtime, sync::Semaphore};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    runtime::Runtime,
+    sync::Semaphore,
+};
 use tokio_util::sync::CancellationToken;
 
 /// A simple resource downloader
@@ -43,6 +53,29 @@ pub struct KDownloader {
     #[builder(default = 0)]
     threads: usize,
 
+    /// Number of times to retry a file that fails with a transient error
+    /// before giving up on it
+    #[arg(long, default_value_t = 3)]
+    #[builder(default = 3)]
+    retries: usize,
+
+    /// Base delay between retries, doubled after every attempt
+    #[arg(long, default_value = "500ms", value_parser = humantime::parse_duration)]
+    #[builder(default = Duration::from_millis(500))]
+    retry_backoff: Duration,
+
+    /// Abort the whole run as soon as a single file fails instead of
+    /// continuing with the remaining files
+    #[arg(long, default_value_t = false)]
+    #[builder(default = false)]
+    fail_fast: bool,
+
+    /// Only verify the hashes of already-downloaded files, without
+    /// downloading anything
+    #[arg(long, default_value_t = false)]
+    #[builder(default = false)]
+    verify_only: bool,
+
     #[arg(skip = None)]
     reporter: Option<Arc<dyn Reporter + Send + Sync>>,
 }
@@ -106,7 +139,20 @@ impl KDownloader {
             .sum::<usize>();
 
         let cancellation_token = CancellationToken::new();
+        let interrupted = Arc::new(AtomicBool::new(false));
+        tokio::spawn(Self::watch_for_shutdown(
+            cancellation_token.clone(),
+            interrupted.clone(),
+        ));
+
+        if self.verify_only {
+            return self
+                .verify_all(resource_info.files, total, total_len, cancellation_token, interrupted)
+                .await;
+        }
+
         let semaphore = Arc::new(Semaphore::new(self.concurrency));
+        let overall_done = Arc::new(AtomicU64::new(0));
         let mut handles = Vec::new();
         for file in resource_info.files {
             let permit = match tokio::select! {
@@ -119,42 +165,92 @@ impl KDownloader {
             let client = client.clone();
             let output_path = self.output.clone();
             let cancellation_token = cancellation_token.clone();
+            let interrupted = interrupted.clone();
             let reporter = self.reporter.clone();
+            let retries = self.retries;
+            let retry_backoff = self.retry_backoff;
+            let fail_fast = self.fail_fast;
+            let overall_done = overall_done.clone();
 
             let handle = tokio::spawn(async move {
                 // Keep the permit alive for the duration of the task
                 let _permit = permit;
+                let path = file.path.clone();
 
                 let status = tokio::select! {
                     _ = cancellation_token.cancelled() => {
-                        Status::Cancelled
+                        if interrupted.load(Ordering::SeqCst) {
+                            Status::Interrupted
+                        } else {
+                            Status::Cancelled
+                        }
                     }
-                    result = file.fetch(client, output_path) => {
+                    result = file.fetch_with_retry(
+                        client,
+                        output_path,
+                        &cancellation_token,
+                        retries,
+                        retry_backoff,
+                        reporter.clone(),
+                        overall_done,
+                        total_len as u64,
+                    ) => {
                         match result {
+                            // `fetch_with_retry` also selects on `cancellation_token`
+                            // while sleeping out a backoff, so a shutdown landing
+                            // during that sleep can resolve this branch with the
+                            // original transient error instead of the outer
+                            // cancellation branch above (tokio::select! ties break
+                            // arbitrarily). Check the token here too so that race
+                            // doesn't get misreported as a real failure.
+                            Err(_) if cancellation_token.is_cancelled() => {
+                                if interrupted.load(Ordering::SeqCst) {
+                                    Status::Interrupted
+                                } else {
+                                    Status::Cancelled
+                                }
+                            }
                             Err(err) => {
-                                // On error, cancel all other tasks
-                                cancellation_token.cancel();
-                                return Err(err);
+                                if fail_fast {
+                                    // On error, cancel all other tasks
+                                    cancellation_token.cancel();
+                                    return Err(err);
+                                }
+                                Status::Failed(err)
                             }
                             Ok(res) => res,
                         }
                     }
                 };
 
+                let failed = matches!(status, Status::Failed(_)).then_some(path);
+
                 if let Some(reporter) = reporter {
                     reporter.report(file, status, total, total_len);
                 }
 
-                Ok(())
+                Ok(failed)
             });
 
             handles.push(handle);
         }
 
+        let mut failed_paths = Vec::new();
         for handle in handles {
-            handle
+            if let Some(path) = handle
                 .await
-                .map_err(|err| Error::InternalError(err.to_string()))??;
+                .map_err(|err| Error::InternalError(err.to_string()))??
+            {
+                failed_paths.push(path);
+            }
+        }
+
+        if !failed_paths.is_empty() {
+            return Err(Error::DownloadsFailed(format!(
+                "{} file(s) failed to download: {}",
+                failed_paths.len(),
+                failed_paths.join(", ")
+            )));
         }
 
         if let Some(ri_bin) = ri_bin {
@@ -164,30 +260,382 @@ impl KDownloader {
 
         Ok(())
     }
+
+    /// Waits for Ctrl-C (and SIGTERM on unix), then marks the run as
+    /// user-interrupted and cancels `cancellation_token`. Lets in-flight
+    /// tasks unwind cleanly, leaving `.part` files in place for a resume.
+    async fn watch_for_shutdown(cancellation_token: CancellationToken, interrupted: Arc<AtomicBool>) {
+        #[cfg(unix)]
+        {
+            let Ok(mut sigterm) = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) else {
+                return;
+            };
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = sigterm.recv() => {}
+            }
+        }
+        #[cfg(not(unix))]
+        {
+            if tokio::signal::ctrl_c().await.is_err() {
+                return;
+            }
+        }
+
+        interrupted.store(true, Ordering::SeqCst);
+        cancellation_token.cancel();
+    }
+
+    /// Hashes every already-downloaded file against its expected sum,
+    /// without downloading anything. Reuses `concurrency` as the size of
+    /// the worker pool so hashing many large files is parallelized.
+    ///
+    /// Shares the same `cancellation_token`/`interrupted` as a regular run,
+    /// so Ctrl-C/SIGTERM stops queuing new files and reports the in-flight
+    /// ones as `Cancelled`/`Interrupted` instead of verification failures.
+    async fn verify_all(
+        &self,
+        files: Vec<FileResource>,
+        total: usize,
+        total_len: usize,
+        cancellation_token: CancellationToken,
+        interrupted: Arc<AtomicBool>,
+    ) -> Result<(), Error> {
+        let semaphore = Arc::new(Semaphore::new(self.concurrency));
+        let mut handles = Vec::new();
+        for file in files {
+            let permit = match tokio::select! {
+                _ = cancellation_token.cancelled() => None,
+                permit = semaphore.clone().acquire_owned() => permit.ok(),
+            } {
+                Some(permit) => permit,
+                None => break,
+            };
+            let output_path = self.output.clone();
+            let reporter = self.reporter.clone();
+            let cancellation_token = cancellation_token.clone();
+            let interrupted = interrupted.clone();
+
+            let handle = tokio::spawn(async move {
+                // Keep the permit alive for the duration of the task
+                let _permit = permit;
+                let path = file.path.clone();
+
+                let status = tokio::select! {
+                    _ = cancellation_token.cancelled() => {
+                        if interrupted.load(Ordering::SeqCst) {
+                            Status::Interrupted
+                        } else {
+                            Status::Cancelled
+                        }
+                    }
+                    status = file.verify(&output_path) => status?,
+                };
+                let failed = matches!(status, Status::Missing | Status::Corrupt);
+
+                if let Some(reporter) = reporter {
+                    reporter.report(file, status, total, total_len);
+                }
+
+                Ok::<_, Error>(failed.then_some(path))
+            });
+
+            handles.push(handle);
+        }
+
+        let mut failed_paths = Vec::new();
+        for handle in handles {
+            if let Some(path) = handle
+                .await
+                .map_err(|err| Error::InternalError(err.to_string()))??
+            {
+                failed_paths.push(path);
+            }
+        }
+
+        if !failed_paths.is_empty() {
+            return Err(Error::VerificationFailed(format!(
+                "{} file(s) failed verification: {}",
+                failed_paths.len(),
+                failed_paths.join(", ")
+            )));
+        }
+
+        Ok(())
+    }
 }
 
 impl FileResource {
-    async fn fetch(&self, client: Client, output_path: PathBuf) -> Result<Status, Error> {
-        let output_path = output_path.join(&self.path);
-        if let Ok(content) = tokio::fs::read(&output_path).await {
-            // Compare hashes
-            let hash = format!("{:x}", Sha256::digest(&content));
-            if hash == self.sum {
-                // File is already up to date
-                return Ok(Status::Skipped);
+    /// Retries [`Self::fetch`] on transient errors (timeouts, connection
+    /// resets, 5xx responses) with exponential backoff and jitter, giving up
+    /// after `retries` retries (`retries + 1` attempts in all) or as soon as
+    /// the run is cancelled.
+    async fn fetch_with_retry(
+        &self,
+        client: Client,
+        output_path: PathBuf,
+        cancellation_token: &CancellationToken,
+        retries: usize,
+        retry_backoff: Duration,
+        reporter: Option<Arc<dyn Reporter + Send + Sync>>,
+        overall_done: Arc<AtomicU64>,
+        overall_total: u64,
+    ) -> Result<Status, Error> {
+        let mut attempt = 0;
+        loop {
+            match self
+                .fetch(
+                    client.clone(),
+                    output_path.clone(),
+                    reporter.clone(),
+                    overall_done.clone(),
+                    overall_total,
+                    attempt == 0,
+                )
+                .await
+            {
+                Ok(status) => return Ok(status),
+                Err(err) if attempt < retries && is_transient(&err) => {
+                    // Cap the exponent so neither the shift nor the
+                    // `Duration` multiplication below can overflow/panic for
+                    // a large user-supplied `--retries`.
+                    let exponent = attempt.min(20) as u32;
+                    let backoff = retry_backoff.saturating_mul(1u32 << exponent);
+                    let jitter = Duration::from_millis(rand::random::<u64>() % 100);
+                    attempt += 1;
+
+                    tokio::select! {
+                        _ = cancellation_token.cancelled() => return Err(err),
+                        _ = tokio::time::sleep(backoff.saturating_add(jitter)) => {}
+                    }
+                }
+                Err(err) => return Err(err),
             }
         }
+    }
 
-        let response = client.get(&self.url).send().await?.error_for_status()?;
-        let bytes = response.bytes().await?;
+    async fn fetch(
+        &self,
+        client: Client,
+        output_path: PathBuf,
+        reporter: Option<Arc<dyn Reporter + Send + Sync>>,
+        overall_done: Arc<AtomicU64>,
+        overall_total: u64,
+        first_attempt: bool,
+    ) -> Result<Status, Error> {
+        let output_path = output_path.join(&self.path);
+        if self.hash_file(&output_path).await?.as_deref() == Some(Hasher::expected_sum(&self.sum)) {
+            // File is already up to date: count its bytes as done so
+            // `overall_done` still converges on `overall_total` even when
+            // every file is skipped.
+            let file_total = self.size as u64;
+            let overall = overall_done.fetch_add(file_total, Ordering::Relaxed) + file_total;
+            if let Some(reporter) = &reporter {
+                reporter.progress(&self.path, file_total, file_total, overall, overall_total);
+            }
+            return Ok(Status::Skipped);
+        }
 
         if let Some(parent) = output_path.parent() {
             tokio::fs::create_dir_all(parent).await?;
         }
-        tokio::fs::write(&output_path, &bytes).await?;
 
+        let part_path = Self::part_path(&output_path);
+        let (mut hasher, expected_sum) = Hasher::for_sum(&self.sum, self.algo.as_deref())?;
+        let mut existing_len = Self::feed_existing(&part_path, &mut hasher).await?.unwrap_or(0);
+        if first_attempt {
+            // The `.part`'s existing prefix predates this `fetch_with_retry`
+            // call (left over from a previous run), so it never went through
+            // this run's chunk-by-chunk counting: seed the overall counter
+            // with it up front, before any branch below may discount it.
+            overall_done.fetch_add(existing_len, Ordering::Relaxed);
+        }
+
+        let mut use_range = existing_len > 0;
+        let response = loop {
+            let mut request = client.get(&self.url);
+            if use_range {
+                request = request.header(reqwest::header::RANGE, format!("bytes={existing_len}-"));
+            }
+            let response = request.send().await?;
+
+            // A `.part` that's already >= the expected size (e.g. the
+            // process died between the last chunk and the final rename) has
+            // nothing left to request: the server answers with 416. Drop the
+            // stale file and restart the download from scratch instead of
+            // failing forever.
+            if needs_restart(use_range, response.status()) {
+                match tokio::fs::remove_file(&part_path).await {
+                    Ok(()) => {}
+                    Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(err) => return Err(err.into()),
+                }
+                // The discarded `.part` bytes were already folded into
+                // `overall_done` (by the seed on the first attempt, or by
+                // per-chunk counting on whichever earlier attempt wrote
+                // them) — undo that before they get redownloaded and
+                // recounted from zero.
+                Self::discount_existing(&overall_done, existing_len);
+                hasher = Hasher::for_sum(&self.sum, self.algo.as_deref())?.0;
+                existing_len = 0;
+                use_range = false;
+                continue;
+            }
+
+            break response.error_for_status()?;
+        };
+
+        let resuming = is_resumable_response(use_range, response.status());
+        let mut file = if resuming {
+            tokio::fs::OpenOptions::new()
+                .append(true)
+                .open(&part_path)
+                .await?
+        } else {
+            // Server doesn't support ranges (or there's nothing left to resume
+            // from, or it stopped doing so on a retry): start over. As above,
+            // the `.part`'s existing bytes are about to be discarded and
+            // redownloaded, so undo their earlier contribution to
+            // `overall_done` before the stream loop recounts them.
+            Self::discount_existing(&overall_done, existing_len);
+            existing_len = 0;
+            hasher = Hasher::for_sum(&self.sum, self.algo.as_deref())?.0;
+            tokio::fs::File::create(&part_path).await?
+        };
+
+        let file_total = self.size as u64;
+        let mut bytes_done = if resuming { existing_len } else { 0 };
+        if resuming && existing_len > 0 && first_attempt {
+            // Report the resumed prefix, already seeded into the overall
+            // counter above. A same-run retry's prefix is bytes this very
+            // call already streamed and reported, so it isn't reported again.
+            let overall = overall_done.load(Ordering::Relaxed);
+            if let Some(reporter) = &reporter {
+                reporter.progress(&self.path, bytes_done, file_total, overall, overall_total);
+            }
+        }
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            hasher.update(&chunk);
+            file.write_all(&chunk).await?;
+
+            bytes_done += chunk.len() as u64;
+            let overall = overall_done.fetch_add(chunk.len() as u64, Ordering::Relaxed) + chunk.len() as u64;
+            if let Some(reporter) = &reporter {
+                reporter.progress(&self.path, bytes_done, file_total, overall, overall_total);
+            }
+        }
+        file.flush().await?;
+
+        let hash = hasher.finalize();
+        if hash != expected_sum {
+            tokio::fs::remove_file(&part_path).await?;
+            return Err(Error::HashMismatch {
+                path: self.path.clone(),
+                expected: expected_sum,
+                actual: hash,
+            });
+        }
+
+        tokio::fs::rename(&part_path, &output_path).await?;
         Ok(Status::Downloaded)
     }
+
+    /// Streams `path` through the digest algorithm picked for this resource
+    /// without ever holding the whole file in memory, returning `None` if
+    /// the file doesn't exist yet.
+    async fn hash_file(&self, path: &Path) -> Result<Option<String>, Error> {
+        let (mut hasher, _) = Hasher::for_sum(&self.sum, self.algo.as_deref())?;
+        match Self::feed_existing(path, &mut hasher).await? {
+            Some(_) => Ok(Some(hasher.finalize())),
+            None => Ok(None),
+        }
+    }
+
+    /// Feeds the existing content of `path`, if any, into `hasher` and
+    /// returns the number of bytes fed, or `None` if the file doesn't exist.
+    /// Used both for the up-to-date check and to seed the hasher with the
+    /// bytes already written to a `.part` file before resuming a download.
+    async fn feed_existing(path: &Path, hasher: &mut Hasher) -> Result<Option<u64>, Error> {
+        let mut file = match tokio::fs::File::open(path).await {
+            Ok(file) => file,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut buf = vec![0u8; 64 * 1024];
+        let mut total = 0u64;
+        loop {
+            let read = file.read(&mut buf).await?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buf[..read]);
+            total += read as u64;
+        }
+
+        Ok(Some(total))
+    }
+
+    fn part_path(output_path: &Path) -> PathBuf {
+        let mut part_path = output_path.as_os_str().to_os_string();
+        part_path.push(".part");
+        part_path.into()
+    }
+
+    /// Removes `existing_len` bytes of a just-discarded `.part` prefix from
+    /// `overall_done`. Every byte on disk was already counted once, either
+    /// by the cross-process-resume seed or by the per-chunk counting in the
+    /// attempt that streamed it, so throwing the prefix away and
+    /// redownloading it from scratch must undo that earlier count first.
+    fn discount_existing(overall_done: &AtomicU64, existing_len: u64) {
+        if existing_len > 0 {
+            overall_done.fetch_sub(existing_len, Ordering::Relaxed);
+        }
+    }
+
+    /// Hashes the on-disk file against `self.sum` without downloading
+    /// anything, for `verify_only` runs.
+    async fn verify(&self, output_path: &Path) -> Result<Status, Error> {
+        let output_path = output_path.join(&self.path);
+        match self.hash_file(&output_path).await? {
+            None => Ok(Status::Missing),
+            Some(hash) if hash == Hasher::expected_sum(&self.sum) => Ok(Status::Verified),
+            Some(_) => Ok(Status::Corrupt),
+        }
+    }
+}
+
+/// Whether an error is likely to succeed on retry: timeouts, connection
+/// resets and 5xx responses, as opposed to e.g. a 404 or a hash mismatch.
+fn is_transient(err: &Error) -> bool {
+    match err {
+        Error::Reqwest(err) => {
+            err.is_timeout()
+                || err.is_connect()
+                // A connection reset mid-download (as opposed to at connect
+                // time) surfaces as a request/body error, not `is_connect()`
+                || err.is_request()
+                || err.is_body()
+                || err.status().is_some_and(|status| status.is_server_error())
+        }
+        _ => false,
+    }
+}
+
+/// Whether a `.part` that carried a `Range` header must be dropped and the
+/// download restarted from scratch: the server rejected the range entirely,
+/// typically because the `.part` already holds >= the expected size.
+fn needs_restart(use_range: bool, status: reqwest::StatusCode) -> bool {
+    use_range && status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE
+}
+
+/// Whether the server actually resumed the `.part` at the requested offset,
+/// as opposed to ignoring the `Range` header and sending the whole file
+/// again (some servers reply `200 OK` rather than erroring).
+fn is_resumable_response(use_range: bool, status: reqwest::StatusCode) -> bool {
+    use_range && status == reqwest::StatusCode::PARTIAL_CONTENT
 }
 
 impl Debug for KDownloader {
@@ -197,6 +645,198 @@ impl Debug for KDownloader {
             .field("output", &self.output)
             .field("concurrency", &self.concurrency)
             .field("threads", &self.threads)
+            .field("retries", &self.retries)
+            .field("retry_backoff", &self.retry_backoff)
+            .field("fail_fast", &self.fail_fast)
+            .field("verify_only", &self.verify_only)
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response_with_status(status: reqwest::StatusCode) -> reqwest::Response {
+        http::Response::builder()
+            .status(status)
+            .body(Vec::new())
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn is_transient_retries_server_errors() {
+        let err = Error::Reqwest(
+            response_with_status(reqwest::StatusCode::SERVICE_UNAVAILABLE)
+                .error_for_status()
+                .unwrap_err(),
+        );
+        assert!(is_transient(&err));
+    }
+
+    #[test]
+    fn is_transient_does_not_retry_client_errors() {
+        let err = Error::Reqwest(
+            response_with_status(reqwest::StatusCode::NOT_FOUND)
+                .error_for_status()
+                .unwrap_err(),
+        );
+        assert!(!is_transient(&err));
+    }
+
+    #[test]
+    fn is_transient_does_not_retry_non_network_errors() {
+        let err = Error::HashMismatch {
+            path: "foo".into(),
+            expected: "a".into(),
+            actual: "b".into(),
+        };
+        assert!(!is_transient(&err));
+    }
+
+    #[test]
+    fn needs_restart_only_when_a_range_request_was_rejected() {
+        assert!(needs_restart(true, reqwest::StatusCode::RANGE_NOT_SATISFIABLE));
+        assert!(!needs_restart(false, reqwest::StatusCode::RANGE_NOT_SATISFIABLE));
+        assert!(!needs_restart(true, reqwest::StatusCode::OK));
+    }
+
+    #[test]
+    fn is_resumable_response_requires_both_a_range_request_and_a_206() {
+        assert!(is_resumable_response(true, reqwest::StatusCode::PARTIAL_CONTENT));
+        // The server ignored our Range header and sent the whole file again.
+        assert!(!is_resumable_response(true, reqwest::StatusCode::OK));
+        assert!(!is_resumable_response(false, reqwest::StatusCode::PARTIAL_CONTENT));
+    }
+
+    fn file_resource(path: &str, sum: String) -> FileResource {
+        FileResource {
+            path: path.to_owned(),
+            version: 0,
+            size: 5,
+            sum,
+            url: String::new(),
+            algo: None,
+        }
+    }
+
+    async fn with_temp_dir<F, Fut>(name: &str, test: F)
+    where
+        F: FnOnce(PathBuf) -> Fut,
+        Fut: std::future::Future<Output = ()>,
+    {
+        let dir = std::env::temp_dir().join(format!("konaste_downloader_test_{name}"));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        test(dir.clone()).await;
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+
+    #[tokio::test]
+    async fn verify_reports_missing_for_an_absent_file() {
+        with_temp_dir("missing", |dir| async move {
+            let file = file_resource("absent.bin", "deadbeef".into());
+            assert!(matches!(file.verify(&dir).await.unwrap(), Status::Missing));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn verify_reports_verified_when_the_hash_matches() {
+        use sha2::Digest;
+
+        with_temp_dir("verified", |dir| async move {
+            let contents = b"hello";
+            let sum = format!("{:x}", sha2::Sha256::digest(contents));
+            let file = file_resource("ok.bin", sum);
+
+            tokio::fs::write(dir.join(&file.path), contents).await.unwrap();
+            assert!(matches!(file.verify(&dir).await.unwrap(), Status::Verified));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn verify_reports_corrupt_when_the_hash_does_not_match() {
+        with_temp_dir("corrupt", |dir| async move {
+            let file = file_resource("bad.bin", "0".repeat(64));
+
+            tokio::fs::write(dir.join(&file.path), b"hello").await.unwrap();
+            assert!(matches!(file.verify(&dir).await.unwrap(), Status::Corrupt));
+        })
+        .await;
+    }
+
+    /// A minimal raw-socket HTTP server that serves exactly two requests to
+    /// drive [`FileResource::fetch_with_retry`] through a resume, a
+    /// mid-stream failure and a retry that falls back to a non-range `200`:
+    /// the scenario `overall_done` previously double-counted.
+    async fn serve_resume_then_redownload(listener: tokio::net::TcpListener, contents: &'static [u8], resumed_at: usize) {
+        // First request: a Range resume. Answer 206, stream a few more bytes
+        // than already on disk, then drop the connection before the
+        // promised `Content-Length` is satisfied to simulate a transient
+        // mid-stream failure.
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buf = [0u8; 1024];
+        socket.read(&mut buf).await.unwrap();
+        let remaining = &contents[resumed_at..];
+        let headers = format!("HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\n\r\n", remaining.len());
+        socket.write_all(headers.as_bytes()).await.unwrap();
+        socket.write_all(&remaining[..5]).await.unwrap();
+        drop(socket);
+
+        // Second request (the retry): the server stops honoring `Range` and
+        // sends the whole file back with a plain `200`.
+        let (mut socket, _) = listener.accept().await.unwrap();
+        socket.read(&mut buf).await.unwrap();
+        let headers = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n", contents.len());
+        socket.write_all(headers.as_bytes()).await.unwrap();
+        socket.write_all(contents).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn overall_done_does_not_overshoot_when_a_resume_falls_back_to_a_full_redownload() {
+        use sha2::Digest;
+
+        with_temp_dir("overshoot", |dir| async move {
+            let contents: &'static [u8] = b"hello world! this is the full file contents.";
+            let sum = format!("{:x}", sha2::Sha256::digest(contents));
+            let file = file_resource("overshoot.bin", sum);
+            let file_total = contents.len() as u64;
+
+            // Seed a `.part` with a prefix, as if an earlier run already
+            // streamed (and counted) it before being interrupted.
+            let resumed_at = 10;
+            let part_path = FileResource::part_path(&dir.join(&file.path));
+            tokio::fs::write(&part_path, &contents[..resumed_at]).await.unwrap();
+
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            let server = tokio::spawn(serve_resume_then_redownload(listener, contents, resumed_at));
+
+            let file = FileResource { url: format!("http://{addr}/"), ..file };
+            let overall_done = Arc::new(AtomicU64::new(0));
+            let token = CancellationToken::new();
+
+            let status = file
+                .fetch_with_retry(
+                    reqwest::Client::new(),
+                    dir.clone(),
+                    &token,
+                    1,
+                    Duration::from_millis(1),
+                    None,
+                    overall_done.clone(),
+                    file_total,
+                )
+                .await
+                .unwrap();
+
+            server.await.unwrap();
+
+            assert!(matches!(status, Status::Downloaded));
+            assert_eq!(overall_done.load(Ordering::Relaxed), file_total);
+        })
+        .await;
+    }
+}
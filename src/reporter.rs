@@ -1,11 +1,28 @@
-use crate::resources::FileResource;
+use crate::{error::Error, resources::FileResource};
 
 pub trait Reporter {
     fn report(&self, file: FileResource, status: Status, total_files: usize, total_bytes: usize);
+
+    /// Called as chunks of a file land, to drive a live progress bar.
+    /// `bytes_done`/`file_total` track the current file, `overall_done`/
+    /// `overall_total` the whole run. No-op by default.
+    fn progress(&self, path: &str, bytes_done: u64, file_total: u64, overall_done: u64, overall_total: u64) {
+        let _ = (path, bytes_done, file_total, overall_done, overall_total);
+    }
 }
 
 pub enum Status {
     Downloaded,
     Skipped,
+    /// Aborted because another file failed and `fail_fast` cancelled the run.
     Cancelled,
+    /// Aborted because the user requested a shutdown (Ctrl-C / SIGTERM).
+    Interrupted,
+    Failed(Error),
+    /// `verify_only`: the on-disk file's hash matches `sum`.
+    Verified,
+    /// `verify_only`: the file doesn't exist on disk.
+    Missing,
+    /// `verify_only`: the file exists but its hash doesn't match `sum`.
+    Corrupt,
 }
@@ -18,4 +18,8 @@ pub struct FileResource {
     pub sum: String,
     #[serde(default)]
     pub url: String,
+    /// Digest algorithm used for `sum`, e.g. `"sha256"` or `"blake3"`.
+    /// Overrides the automatic detection based on the `sum` format.
+    #[serde(default)]
+    pub algo: Option<String>,
 }